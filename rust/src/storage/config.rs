@@ -13,6 +13,8 @@ use url::Url;
 
 #[cfg(any(feature = "s3", feature = "s3-native-tls"))]
 use super::s3::{S3StorageBackend, S3StorageOptions};
+#[cfg(feature = "hf")]
+use super::hf::HuggingFaceStorageBackend;
 #[cfg(feature = "hdfs")]
 use datafusion_objectstore_hdfs::object_store::hdfs::HadoopFileSystem;
 #[cfg(any(feature = "s3", feature = "s3-native-tls"))]
@@ -28,6 +30,12 @@ use object_store::gcp::{GoogleCloudStorageBuilder, GoogleConfigKey};
     feature = "azure"
 ))]
 use std::str::FromStr;
+#[cfg(any(feature = "s3", feature = "s3-native-tls"))]
+use super::sign::S3SignedUrlGenerator;
+#[cfg(feature = "azure")]
+use super::sign::AzureSignedUrlGenerator;
+#[cfg(any(feature = "s3", feature = "s3-native-tls", feature = "azure"))]
+use super::sign::SignedUrlGenerator;
 
 /// Options used for configuring backend storage
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -50,9 +58,16 @@ impl StorageOptions {
     }
 
     /// Denotes if unsecure connections via http are allowed
+    ///
+    /// This is also implied when an explicit `endpoint`/`aws_endpoint` option
+    /// points at a plain-`http://` url, which is the common case for local
+    /// MinIO dev setups that never bothered with a certificate.
     pub fn allow_http(&self) -> bool {
         self.0.iter().any(|(key, value)| {
             key.to_ascii_lowercase().contains("allow_http") & str_is_truthy(value)
+        }) || self.0.iter().any(|(key, value)| {
+            let key = key.to_ascii_lowercase();
+            (key == "endpoint" || key == "aws_endpoint") && value.starts_with("http://")
         })
     }
 
@@ -68,6 +83,26 @@ impl StorageOptions {
             .collect()
     }
 
+    /// Like [`as_azure_options`](Self::as_azure_options), but rejects unknown
+    /// keys instead of silently dropping them, so a typo'd or misplaced
+    /// option fails at table-open time rather than surfacing as a
+    /// mysterious credential error.
+    #[cfg(feature = "azure")]
+    pub fn try_as_azure_options(&self) -> DeltaResult<HashMap<AzureConfigKey, String>> {
+        self.0
+            .iter()
+            .filter(|(key, _)| !is_cross_cutting_option(key, &[]))
+            .map(|(key, value)| {
+                AzureConfigKey::from_str(&key.to_ascii_lowercase())
+                    .map(|az_key| (az_key, value.clone()))
+                    .map_err(|_| DeltaTableError::UnknownConfigurationKey {
+                        store: "azure".into(),
+                        key: key.clone(),
+                    })
+            })
+            .collect()
+    }
+
     /// Subset of options relevant for s3 storage
     #[cfg(any(feature = "s3", feature = "s3-native-tls"))]
     pub fn as_s3_options(&self) -> HashMap<AmazonS3ConfigKey, String> {
@@ -80,6 +115,41 @@ impl StorageOptions {
             .collect()
     }
 
+    /// Like [`as_s3_options`](Self::as_s3_options), but rejects unknown keys
+    /// instead of silently dropping them. Also tolerates the S3-specific
+    /// options this module reads directly rather than forwarding to
+    /// [`AmazonS3Builder`] (`account_id` for R2, `minio` as a provider
+    /// marker), as well as the delta-specific keys [`S3StorageOptions`]
+    /// consumes for unsafe-rename and DynamoDB locking behavior - those
+    /// are real, supported keys, just not part of `AmazonS3ConfigKey`.
+    #[cfg(any(feature = "s3", feature = "s3-native-tls"))]
+    pub fn try_as_s3_options(&self) -> DeltaResult<HashMap<AmazonS3ConfigKey, String>> {
+        const S3_EXTRA_OPTIONS: &[&str] = &[
+            "account_id",
+            "minio",
+            "aws_s3_allow_unsafe_rename",
+            "aws_s3_locking_provider",
+            "dynamo_lock_table_name",
+            "dynamo_lock_owner_name",
+            "dynamo_lock_partition_key_value",
+            "dynamo_lock_lease_duration",
+            "dynamo_lock_refresh_period_millis",
+            "dynamo_lock_additional_time_to_wait_millis",
+        ];
+        self.0
+            .iter()
+            .filter(|(key, _)| !is_cross_cutting_option(key, S3_EXTRA_OPTIONS))
+            .map(|(key, value)| {
+                AmazonS3ConfigKey::from_str(&key.to_ascii_lowercase())
+                    .map(|s3_key| (s3_key, value.clone()))
+                    .map_err(|_| DeltaTableError::UnknownConfigurationKey {
+                        store: "s3".into(),
+                        key: key.clone(),
+                    })
+            })
+            .collect()
+    }
+
     /// Subset of options relevant for gcs storage
     #[cfg(feature = "gcs")]
     pub fn as_gcs_options(&self) -> HashMap<GoogleConfigKey, String> {
@@ -91,6 +161,39 @@ impl StorageOptions {
             })
             .collect()
     }
+
+    /// Like [`as_gcs_options`](Self::as_gcs_options), but rejects unknown
+    /// keys instead of silently dropping them.
+    #[cfg(feature = "gcs")]
+    pub fn try_as_gcs_options(&self) -> DeltaResult<HashMap<GoogleConfigKey, String>> {
+        self.0
+            .iter()
+            .filter(|(key, _)| !is_cross_cutting_option(key, &[]))
+            .map(|(key, value)| {
+                GoogleConfigKey::from_str(&key.to_ascii_lowercase())
+                    .map(|gcs_key| (gcs_key, value.clone()))
+                    .map_err(|_| DeltaTableError::UnknownConfigurationKey {
+                        store: "gcs".into(),
+                        key: key.clone(),
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Keys that are either handled cross-cutting (outside any single backend's
+/// config key enum) or consumed directly by this module for `store_specific`
+/// reasons, and so should never be rejected as "unknown" by the `try_as_*`
+/// validators.
+#[cfg(any(
+    feature = "s3",
+    feature = "s3-native-tls",
+    feature = "gcs",
+    feature = "azure"
+))]
+fn is_cross_cutting_option(key: &str, store_specific: &[&str]) -> bool {
+    let key = key.to_ascii_lowercase();
+    key == "allow_http" || store_specific.iter().any(|k| k.eq_ignore_ascii_case(&key))
 }
 
 impl From<HashMap<String, String>> for StorageOptions {
@@ -99,6 +202,42 @@ impl From<HashMap<String, String>> for StorageOptions {
     }
 }
 
+/// Build an [`ObjectStore`] and the in-store [`Path`] for an arbitrary delta
+/// url in one call.
+///
+/// This is the public counterpart of `ObjectStoreKind::parse_url(..).into_impl(..)`,
+/// following the `{scheme}://{bucket}/{path}` -> `(store, path)` factory
+/// pattern the wider object_store ecosystem has converged on, so integrators
+/// don't need to reimplement scheme matching just to construct a backend
+/// from a delta url.
+///
+/// `into_impl` already wraps the backend in a `PrefixStore` rooted at
+/// `url.path()` (see `url_prefix_handler`), so the returned store's keys are
+/// relative to the table root already - the returned [`Path`] is therefore
+/// the table root itself (`Path::default()`), not `url.path()` again, to
+/// avoid applying that prefix twice.
+pub fn parse_url_opts(
+    url: &Url,
+    options: impl Into<StorageOptions>,
+) -> DeltaResult<(Arc<DynObjectStore>, Path)> {
+    let options = options.into();
+    let kind = ObjectStoreKind::parse_url(url, &options)?;
+    let store = kind.into_impl(url, options)?;
+    Ok((store, Path::default()))
+}
+
+/// Build a [`SignedUrlGenerator`] for an arbitrary delta url in one call,
+/// the signing counterpart of [`parse_url_opts`].
+#[cfg(any(feature = "s3", feature = "s3-native-tls", feature = "azure"))]
+pub fn signed_url_generator(
+    url: &Url,
+    options: impl Into<StorageOptions>,
+) -> DeltaResult<Arc<dyn SignedUrlGenerator>> {
+    let options = options.into();
+    let kind = ObjectStoreKind::parse_url(url, &options)?;
+    kind.into_signer(url, options)
+}
+
 pub(crate) enum ObjectStoreKind {
     Local,
     InMemory,
@@ -106,17 +245,19 @@ pub(crate) enum ObjectStoreKind {
     Google,
     Azure,
     Hdfs,
+    HuggingFace,
 }
 
 impl ObjectStoreKind {
-    pub fn parse_url(url: &Url) -> DeltaResult<Self> {
+    pub fn parse_url(url: &Url, options: &StorageOptions) -> DeltaResult<Self> {
         match url.scheme() {
             "file" => Ok(ObjectStoreKind::Local),
             "memory" => Ok(ObjectStoreKind::InMemory),
             "az" | "abfs" | "abfss" | "azure" | "wasb" | "adl" => Ok(ObjectStoreKind::Azure),
-            "s3" | "s3a" => Ok(ObjectStoreKind::S3),
+            "s3" | "s3a" | "r2" => Ok(ObjectStoreKind::S3),
             "gs" => Ok(ObjectStoreKind::Google),
             "hdfs" => Ok(ObjectStoreKind::Hdfs),
+            "hf" => Ok(ObjectStoreKind::HuggingFace),
             "https" => {
                 let host = url.host_str().unwrap_or_default();
                 if host.contains("amazonaws.com") {
@@ -125,6 +266,14 @@ impl ObjectStoreKind {
                     || host.contains("blob.core.windows.net")
                 {
                     Ok(ObjectStoreKind::Azure)
+                } else if has_option_key(options, "endpoint")
+                    || has_option_key(options, "aws_endpoint")
+                    || is_minio_marker(host, options)
+                {
+                    // An explicit endpoint (or a recognized MinIO deployment)
+                    // means this is an S3-compatible provider rather than an
+                    // unknown https url.
+                    Ok(ObjectStoreKind::S3)
                 } else {
                     Err(DeltaTableError::Generic(format!(
                         "unsupported url: {}",
@@ -153,9 +302,36 @@ impl ObjectStoreKind {
             ObjectStoreKind::InMemory => Ok(Self::url_prefix_handler(InMemory::new(), storage_url)),
             #[cfg(any(feature = "s3", feature = "s3-native-tls"))]
             ObjectStoreKind::S3 => {
-                let amazon_s3 = AmazonS3Builder::from_env()
-                    .with_url(storage_url.as_ref())
-                    .try_with_options(&_options.as_s3_options())?
+                let mut builder = AmazonS3Builder::from_env();
+                if storage_url.scheme() == "r2" {
+                    // `r2://{bucket}/...` - the endpoint is derived from the
+                    // account id rather than carried in the url itself.
+                    let account_id = _options
+                        .0
+                        .iter()
+                        .find(|(key, _)| key.eq_ignore_ascii_case("account_id"))
+                        .map(|(_, value)| value)
+                        .ok_or_else(|| {
+                            DeltaTableError::Generic(
+                                "r2:// urls require an `account_id` option to derive the endpoint"
+                                    .into(),
+                            )
+                        })?;
+                    let bucket = storage_url.host_str().ok_or_else(|| {
+                        DeltaTableError::Generic(format!(
+                            "missing bucket name in {}",
+                            storage_url.as_str()
+                        ))
+                    })?;
+                    builder = builder
+                        .with_endpoint(format!("https://{account_id}.r2.cloudflarestorage.com"))
+                        .with_bucket_name(bucket)
+                        .with_region("auto");
+                } else {
+                    builder = builder.with_url(storage_url.as_ref());
+                }
+                let amazon_s3 = builder
+                    .try_with_options(&_options.try_as_s3_options()?)?
                     .with_allow_http(_options.allow_http())
                     .build()?;
                 let store = S3StorageBackend::try_new(
@@ -173,7 +349,7 @@ impl ObjectStoreKind {
             ObjectStoreKind::Azure => {
                 let store = MicrosoftAzureBuilder::from_env()
                     .with_url(storage_url.as_ref())
-                    .try_with_options(&_options.as_azure_options())?
+                    .try_with_options(&_options.try_as_azure_options()?)?
                     .with_allow_http(_options.allow_http())
                     .build()?;
                 Ok(Self::url_prefix_handler(store, storage_url))
@@ -187,7 +363,7 @@ impl ObjectStoreKind {
             ObjectStoreKind::Google => {
                 let store = GoogleCloudStorageBuilder::from_env()
                     .with_url(storage_url.as_ref())
-                    .try_with_options(&_options.as_gcs_options())?
+                    .try_with_options(&_options.try_as_gcs_options()?)?
                     .build()?;
                 Ok(Self::url_prefix_handler(store, storage_url))
             }
@@ -211,6 +387,58 @@ impl ObjectStoreKind {
                 feature: "hdfs",
                 url: storage_url.as_ref().into(),
             }),
+            #[cfg(feature = "hf")]
+            ObjectStoreKind::HuggingFace => {
+                // Not run through `url_prefix_handler`: `{org}/{repo}` in the
+                // url authority/path aren't part of the object key, and
+                // `HuggingFaceStorageBackend` already folds the in-repo
+                // table sub-path into its own prefix.
+                let store = HuggingFaceStorageBackend::try_new(storage_url, &_options)?;
+                Ok(Arc::new(store))
+            }
+            #[cfg(not(feature = "hf"))]
+            ObjectStoreKind::HuggingFace => Err(DeltaTableError::MissingFeature {
+                feature: "hf",
+                url: storage_url.as_ref().into(),
+            }),
+        }
+    }
+
+    /// Build a [`SignedUrlGenerator`] that can hand out direct, time-limited
+    /// urls to objects in this backend, for servers that want to redirect
+    /// clients straight to storage (Delta Sharing-style) instead of
+    /// proxying bytes themselves.
+    #[cfg(any(feature = "s3", feature = "s3-native-tls", feature = "azure"))]
+    pub fn into_signer(
+        self,
+        storage_url: &Url,
+        options: impl Into<StorageOptions>,
+    ) -> DeltaResult<Arc<dyn SignedUrlGenerator>> {
+        let _options = options.into();
+        match self {
+            #[cfg(any(feature = "s3", feature = "s3-native-tls"))]
+            ObjectStoreKind::S3 => Ok(Arc::new(S3SignedUrlGenerator::try_new(
+                storage_url,
+                &_options,
+            )?)),
+            #[cfg(not(any(feature = "s3", feature = "s3-native-tls")))]
+            ObjectStoreKind::S3 => Err(DeltaTableError::MissingFeature {
+                feature: "s3",
+                url: storage_url.as_ref().into(),
+            }),
+            #[cfg(feature = "azure")]
+            ObjectStoreKind::Azure => Ok(Arc::new(AzureSignedUrlGenerator::try_new(
+                storage_url,
+                &_options,
+            )?)),
+            #[cfg(not(feature = "azure"))]
+            ObjectStoreKind::Azure => Err(DeltaTableError::MissingFeature {
+                feature: "azure",
+                url: storage_url.as_ref().into(),
+            }),
+            _ => Err(DeltaTableError::Generic(
+                "signed urls are only supported for s3 and azure backends".into(),
+            )),
         }
     }
 
@@ -223,3 +451,27 @@ impl ObjectStoreKind {
         }
     }
 }
+
+/// Case-insensitive lookup, matching how every other option lookup in this
+/// module (`allow_http`, `is_minio_marker`, the `try_as_*` validators)
+/// treats option keys.
+fn has_option_key(options: &StorageOptions, key: &str) -> bool {
+    options.0.keys().any(|k| k.eq_ignore_ascii_case(key))
+}
+
+/// Recognize a handful of well-known MinIO deployment markers so that plain
+/// `https://` urls pointing at a local or self-hosted MinIO instance are
+/// treated as S3-compatible rather than rejected as "unsupported".
+#[cfg(any(feature = "s3", feature = "s3-native-tls"))]
+fn is_minio_marker(host: &str, options: &StorageOptions) -> bool {
+    host.contains("minio")
+        || options
+            .0
+            .iter()
+            .any(|(key, value)| key.eq_ignore_ascii_case("minio") && str_is_truthy(value))
+}
+
+#[cfg(not(any(feature = "s3", feature = "s3-native-tls")))]
+fn is_minio_marker(_host: &str, _options: &StorageOptions) -> bool {
+    false
+}
@@ -0,0 +1,244 @@
+//! Object store implementation for the HuggingFace Hub.
+//!
+//! Tables are addressed as `hf://datasets/{org}/{repo}/path/to/table` and are
+//! resolved against the HF "resolve" endpoint, which re-points at the
+//! underlying CDN. This backend is read-only: the Hub has no concept of
+//! writing individual objects through this API.
+use std::ops::Range;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::http::HttpBuilder;
+use object_store::path::Path;
+use object_store::{
+    ClientOptions, GetOptions, GetResult, ListResult, MultipartId, ObjectMeta, ObjectStore,
+    Result as OsResult,
+};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use tokio::io::AsyncWrite;
+use url::Url;
+
+use crate::{DeltaResult, DeltaTableError};
+
+use super::config::StorageOptions;
+
+const HF_ENDPOINT: &str = "https://huggingface.co";
+const HF_DEFAULT_REVISION: &str = "main";
+
+/// Read-only [`ObjectStore`] implementation backed by a HuggingFace dataset
+/// repository.
+///
+/// Requests are rewritten to the Hub's `resolve` endpoint
+/// (`datasets/{org}/{repo}/resolve/{revision}/{path-to-table}/{path}`) and delegated
+/// to an inner HTTP object store, with an `Authorization: Bearer <token>`
+/// header attached when a token is available.
+///
+/// Unlike the other backends, this one is not additionally wrapped in a
+/// `PrefixStore` by `into_impl`: the table's in-repo sub-path is already
+/// folded into `resolve_prefix` below, since the HF url's authority
+/// (`datasets`) and `{org}/{repo}` don't correspond to an object key at all.
+///
+/// `list`/`list_with_delimiter` are implemented by delegating to the inner
+/// HTTP store, which issues a WebDAV `PROPFIND` against the target URL - but
+/// the HF `resolve` endpoint doesn't support `PROPFIND`, so in practice
+/// `_delta_log` discovery via `list` will not work against this backend even
+/// though `get`/`get_range`/`head` do (a table's log commit can be read once
+/// its path is known). Listing a table's commit history would need to go
+/// through the HF tree/listing API instead; that's left as a follow-up.
+#[derive(Debug)]
+pub struct HuggingFaceStorageBackend {
+    inner: Arc<dyn ObjectStore>,
+    /// `datasets/{org}/{repo}/resolve/{revision}/{path-to-table}` prefix prepended to
+    /// every key
+    resolve_prefix: Path,
+}
+
+impl std::fmt::Display for HuggingFaceStorageBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HuggingFaceStorageBackend({})", self.resolve_prefix)
+    }
+}
+
+impl HuggingFaceStorageBackend {
+    /// Build a new backend for the dataset repo addressed by `storage_url`
+    /// (e.g. `hf://datasets/org/repo/some/table`).
+    pub fn try_new(storage_url: &Url, options: &StorageOptions) -> DeltaResult<Self> {
+        let (org, repo, table_path) = parse_org_repo(storage_url)?;
+        let revision = options
+            .0
+            .get("hf_revision")
+            .cloned()
+            .unwrap_or_else(|| HF_DEFAULT_REVISION.to_string());
+
+        let mut client_options = ClientOptions::new();
+        if let Some(token) = hf_token(options) {
+            let mut value = HeaderValue::from_str(&format!("Bearer {token}"))
+                .map_err(|e| DeltaTableError::Generic(format!("invalid hf_token: {e}")))?;
+            value.set_sensitive(true);
+            let mut headers = HeaderMap::new();
+            headers.insert(AUTHORIZATION, value);
+            client_options = client_options.with_default_headers(headers);
+        }
+        let inner = HttpBuilder::new()
+            .with_url(HF_ENDPOINT)
+            .with_client_options(client_options)
+            .build()
+            .map_err(|e| DeltaTableError::Generic(format!("failed to build hf store: {e}")))?;
+
+        let mut resolve_prefix = Path::from(format!("datasets/{org}/{repo}/resolve/{revision}"));
+        if !table_path.as_ref().is_empty() {
+            resolve_prefix = resolve_prefix.parts().chain(table_path.parts()).collect();
+        }
+
+        Ok(Self {
+            inner: Arc::new(inner),
+            resolve_prefix,
+        })
+    }
+
+    fn full_path(&self, location: &Path) -> Path {
+        self.resolve_prefix
+            .parts()
+            .chain(location.parts())
+            .collect()
+    }
+}
+
+/// Read the `hf_token` option, falling back to `HF_TOKEN` and then
+/// `HUGGING_FACE_HUB_TOKEN`. Public datasets work fine with no token at all.
+fn hf_token(options: &StorageOptions) -> Option<String> {
+    options
+        .0
+        .get("hf_token")
+        .cloned()
+        .or_else(|| std::env::var("HF_TOKEN").ok())
+        .or_else(|| std::env::var("HUGGING_FACE_HUB_TOKEN").ok())
+}
+
+/// Pull `{org}`, `{repo}`, and the remaining in-repo sub-path out of a
+/// `hf://datasets/{org}/{repo}/path/to/table` url. The sub-path (e.g.
+/// `path/to/table`) is the part `url_prefix_handler` would otherwise have
+/// turned into a `PrefixStore` wrapping, which isn't applied for this
+/// backend since `{org}/{repo}` aren't themselves part of the object key.
+fn parse_org_repo(storage_url: &Url) -> DeltaResult<(String, String, Path)> {
+    let mut segments = storage_url
+        .host_str()
+        .into_iter()
+        .chain(storage_url.path_segments().into_iter().flatten())
+        .filter(|s| !s.is_empty());
+
+    let first = segments.next().unwrap_or_default();
+    // Accept both `hf://datasets/org/repo/...` and `hf://org/repo/...`,
+    // since the `datasets` marker may land in the host or the path
+    // depending on how the url was constructed.
+    let (org, repo) = if first == "datasets" {
+        (segments.next(), segments.next())
+    } else {
+        (Some(first.to_string()), segments.next())
+    };
+
+    match (org, repo) {
+        (Some(org), Some(repo)) if !org.is_empty() && !repo.is_empty() => {
+            let table_path = Path::from(segments.collect::<Vec<_>>().join("/"));
+            Ok((org, repo, table_path))
+        }
+        _ => Err(DeltaTableError::Generic(format!(
+            "invalid hf:// url, expected hf://datasets/{{org}}/{{repo}}/...: {}",
+            storage_url.as_str()
+        ))),
+    }
+}
+
+#[async_trait]
+impl ObjectStore for HuggingFaceStorageBackend {
+    async fn put(&self, _location: &Path, _bytes: Bytes) -> OsResult<()> {
+        Err(object_store::Error::NotImplemented)
+    }
+
+    async fn put_multipart(
+        &self,
+        _location: &Path,
+    ) -> OsResult<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+        Err(object_store::Error::NotImplemented)
+    }
+
+    async fn abort_multipart(&self, _location: &Path, _multipart_id: &MultipartId) -> OsResult<()> {
+        Err(object_store::Error::NotImplemented)
+    }
+
+    async fn get(&self, location: &Path) -> OsResult<GetResult> {
+        self.inner.get(&self.full_path(location)).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> OsResult<GetResult> {
+        self.inner.get_opts(&self.full_path(location), options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> OsResult<Bytes> {
+        self.inner.get_range(&self.full_path(location), range).await
+    }
+
+    async fn head(&self, location: &Path) -> OsResult<ObjectMeta> {
+        self.inner.head(&self.full_path(location)).await
+    }
+
+    async fn delete(&self, _location: &Path) -> OsResult<()> {
+        Err(object_store::Error::NotImplemented)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, OsResult<ObjectMeta>> {
+        let prefix = prefix.map(|p| self.full_path(p));
+        self.inner.list(prefix.as_ref())
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> OsResult<ListResult> {
+        let prefix = prefix.map(|p| self.full_path(p));
+        self.inner.list_with_delimiter(prefix.as_ref()).await
+    }
+
+    async fn copy(&self, _from: &Path, _to: &Path) -> OsResult<()> {
+        Err(object_store::Error::NotImplemented)
+    }
+
+    async fn copy_if_not_exists(&self, _from: &Path, _to: &Path) -> OsResult<()> {
+        Err(object_store::Error::NotImplemented)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn resolves_table_relative_keys_without_double_prefix() {
+        let url = Url::parse("hf://datasets/org/repo/path/to/table").unwrap();
+        let options = StorageOptions::new(HashMap::new());
+        let backend = HuggingFaceStorageBackend::try_new(&url, &options).unwrap();
+
+        let resolved = backend.full_path(&Path::from("_delta_log/00000000000000000000.json"));
+
+        assert_eq!(
+            resolved,
+            Path::from(
+                "datasets/org/repo/resolve/main/path/to/table/_delta_log/00000000000000000000.json"
+            )
+        );
+    }
+
+    #[test]
+    fn resolves_repo_root_tables() {
+        let url = Url::parse("hf://datasets/org/repo").unwrap();
+        let options = StorageOptions::new(HashMap::new());
+        let backend = HuggingFaceStorageBackend::try_new(&url, &options).unwrap();
+
+        let resolved = backend.full_path(&Path::from("_delta_log/00000000000000000000.json"));
+
+        assert_eq!(
+            resolved,
+            Path::from("datasets/org/repo/resolve/main/_delta_log/00000000000000000000.json")
+        );
+    }
+}
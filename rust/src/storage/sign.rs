@@ -0,0 +1,368 @@
+//! Signed (presigned) url generation.
+//!
+//! A Delta Sharing-style server wants to hand clients a short-lived, direct
+//! link to a Parquet data file rather than proxying the bytes itself. This
+//! module builds those urls per backend, reusing the same [`StorageOptions`]
+//! already parsed for opening the table.
+use std::time::Duration;
+
+use base64::Engine;
+use chrono::{SecondsFormat, Utc};
+use hmac::{Hmac, Mac};
+use object_store::path::Path;
+use reqwest::Method;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::{DeltaResult, DeltaTableError};
+
+use super::config::StorageOptions;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Generates short-lived, directly-downloadable urls for objects in a
+/// backend store.
+pub trait SignedUrlGenerator {
+    /// Produce a url authorizing `method` against `path`, valid for
+    /// `validity`.
+    fn sign(&self, path: &Path, validity: Duration, method: Method) -> DeltaResult<Url>;
+}
+
+fn lookup(options: &StorageOptions, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|want| {
+        options
+            .0
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(want))
+            .map(|(_, value)| value.clone())
+    })
+}
+
+/// Look up a required credential option, erroring out by name rather than
+/// silently falling back when it's absent. This is a missing/invalid
+/// config value, not a missing feature gate - that's `into_signer`'s job.
+fn require(options: &StorageOptions, keys: &[&str]) -> DeltaResult<String> {
+    lookup(options, keys).ok_or_else(|| {
+        DeltaTableError::Generic(format!(
+            "missing required signing option, expected one of {keys:?}"
+        ))
+    })
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> DeltaResult<Vec<u8>> {
+    let mut mac = HmacSha256::new_from_slice(key)
+        .map_err(|e| DeltaTableError::Generic(format!("invalid hmac key: {e}")))?;
+    mac.update(data.as_bytes());
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+fn sha256_hex(data: &str) -> String {
+    let digest = Sha256::digest(data.as_bytes());
+    hex::encode(digest)
+}
+
+/// AWS SigV4 presigned-url generator, usable against S3 and any
+/// S3-compatible endpoint (R2, MinIO, Backblaze, ...) reachable through the
+/// same [`StorageOptions`] this module already parses.
+pub struct S3SignedUrlGenerator {
+    bucket: String,
+    region: String,
+    /// scheme + host the presigned request is issued against, e.g.
+    /// `https://{bucket}.s3.{region}.amazonaws.com` or a custom `endpoint` option.
+    endpoint: Url,
+    /// whether `bucket` needs to be folded into the request path. Custom
+    /// endpoints (R2, MinIO, Backblaze, ...) are path-style: the endpoint
+    /// host is the provider's API host, not `{bucket}.{host}`, so the
+    /// bucket has to show up in the path instead.
+    path_style: bool,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl S3SignedUrlGenerator {
+    #[cfg(any(feature = "s3", feature = "s3-native-tls"))]
+    pub fn try_new(storage_url: &Url, options: &StorageOptions) -> DeltaResult<Self> {
+        let bucket = storage_url
+            .host_str()
+            .map(str::to_string)
+            .ok_or_else(|| DeltaTableError::Generic("missing bucket in s3 url".into()))?;
+        let is_r2 = storage_url.scheme() == "r2";
+        let region = lookup(options, &["aws_region", "region"])
+            .unwrap_or_else(|| if is_r2 { "auto" } else { "us-east-1" }.into());
+        let (endpoint, path_style) = match lookup(options, &["aws_endpoint", "endpoint"]) {
+            Some(endpoint) => (
+                Url::parse(&endpoint)
+                    .map_err(|e| DeltaTableError::Generic(format!("invalid endpoint: {e}")))?,
+                true,
+            ),
+            // `r2://{bucket}/...` - same endpoint derivation as `into_impl`
+            None if is_r2 => {
+                let account_id = require(options, &["account_id"])?;
+                (
+                    Url::parse(&format!("https://{account_id}.r2.cloudflarestorage.com"))
+                        .map_err(|e| DeltaTableError::Generic(format!("invalid endpoint: {e}")))?,
+                    true,
+                )
+            }
+            None => (
+                Url::parse(&format!("https://{bucket}.s3.{region}.amazonaws.com"))
+                    .map_err(|e| DeltaTableError::Generic(format!("invalid endpoint: {e}")))?,
+                false,
+            ),
+        };
+        Ok(Self {
+            bucket,
+            region,
+            endpoint,
+            path_style,
+            access_key_id: require(options, &["aws_access_key_id", "access_key_id"])?,
+            secret_access_key: require(options, &["aws_secret_access_key", "secret_access_key"])?,
+            session_token: lookup(options, &["aws_session_token", "session_token"]),
+        })
+    }
+
+    fn signing_key(&self, date_stamp: &str) -> DeltaResult<Vec<u8>> {
+        let k_date = hmac_sha256(
+            format!("AWS4{}", self.secret_access_key).as_bytes(),
+            date_stamp,
+        )?;
+        let k_region = hmac_sha256(&k_date, &self.region)?;
+        let k_service = hmac_sha256(&k_region, "s3")?;
+        hmac_sha256(&k_service, "aws4_request")
+    }
+}
+
+impl SignedUrlGenerator for S3SignedUrlGenerator {
+    fn sign(&self, path: &Path, validity: Duration, method: Method) -> DeltaResult<Url> {
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date_stamp = now.format("%Y%m%d").to_string();
+        let credential_scope = format!("{date_stamp}/{}/s3/aws4_request", self.region);
+        let credential = format!("{}/{credential_scope}", self.access_key_id);
+
+        let mut url = self.endpoint.clone();
+        if self.path_style {
+            url.set_path(&format!("/{}/{}", self.bucket, path.as_ref()));
+        } else {
+            url.set_path(&format!("/{}", path.as_ref()));
+        }
+
+        let mut query: Vec<(String, String)> = vec![
+            ("X-Amz-Algorithm".into(), "AWS4-HMAC-SHA256".into()),
+            ("X-Amz-Credential".into(), credential),
+            ("X-Amz-Date".into(), amz_date.clone()),
+            ("X-Amz-Expires".into(), validity.as_secs().to_string()),
+            ("X-Amz-SignedHeaders".into(), "host".into()),
+        ];
+        if let Some(token) = &self.session_token {
+            query.push(("X-Amz-Security-Token".into(), token.clone()));
+        }
+        query.sort_by(|a, b| a.0.cmp(&b.0));
+        let canonical_query = query
+            .iter()
+            .map(|(k, v)| {
+                format!(
+                    "{}={}",
+                    percent_encode(k),
+                    percent_encode(v)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let host = url
+            .host_str()
+            .ok_or_else(|| DeltaTableError::Generic("endpoint has no host".into()))?;
+        // `Url::port()` only returns `Some` when the port was explicit in
+        // the endpoint (i.e. non-default), which is exactly when the client
+        // will send a `Host: host:port` header - matching that here keeps
+        // MinIO/dev endpoints on non-443 ports from getting
+        // `SignatureDoesNotMatch`.
+        let host = match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        };
+        let canonical_headers = format!("host:{host}\n");
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            method.as_str(),
+            url.path(),
+            canonical_query,
+            canonical_headers,
+            "host",
+            "UNSIGNED-PAYLOAD"
+        );
+
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{credential_scope}\n{}",
+            sha256_hex(&canonical_request)
+        );
+
+        let signing_key = self.signing_key(&date_stamp)?;
+        let signature = hex::encode(hmac_sha256(&signing_key, &string_to_sign)?);
+
+        url.set_query(Some(&format!(
+            "{canonical_query}&X-Amz-Signature={signature}"
+        )));
+        Ok(url)
+    }
+}
+
+const AZURE_SAS_SIGNED_VERSION: &str = "2021-08-06";
+
+/// Build the SAS string-to-sign per the Azure "Constructing a service SAS"
+/// spec for `sv >= 2020-12-06` (which `AZURE_SAS_SIGNED_VERSION` is): 16
+/// newline-joined fields - permissions, start, expiry, canonicalized
+/// resource, identifier, ip range (2 empty fields), protocol, version,
+/// resource, snapshot time, encryption scope, then the optional response
+/// headers (cache-control, content-disposition, content-encoding,
+/// content-language, content-type). Pulled out as a pure function so the
+/// exact layout can be pinned down with a known-vector test.
+fn sas_string_to_sign(
+    permissions: &str,
+    start: &str,
+    expiry: &str,
+    canonicalized_resource: &str,
+    signed_version: &str,
+) -> String {
+    format!(
+        "{permissions}\n{start}\n{expiry}\n{canonicalized_resource}\n\n\nhttps\n{signed_version}\nb\n\n\n\n\n\n\n"
+    )
+}
+
+/// Azure SAS (shared access signature) generator.
+pub struct AzureSignedUrlGenerator {
+    account_name: String,
+    account_key: Vec<u8>,
+    container: String,
+}
+
+impl AzureSignedUrlGenerator {
+    #[cfg(feature = "azure")]
+    pub fn try_new(storage_url: &Url, options: &StorageOptions) -> DeltaResult<Self> {
+        let account_name = require(options, &["azure_storage_account_name", "account_name"])?;
+        let account_key_b64 = require(options, &["azure_storage_account_key", "account_key"])?;
+        let account_key = base64::engine::general_purpose::STANDARD
+            .decode(account_key_b64)
+            .map_err(|e| DeltaTableError::Generic(format!("invalid azure account key: {e}")))?;
+        let container = storage_url
+            .host_str()
+            .map(str::to_string)
+            .ok_or_else(|| DeltaTableError::Generic("missing container in azure url".into()))?;
+        Ok(Self {
+            account_name,
+            account_key,
+            container,
+        })
+    }
+}
+
+impl SignedUrlGenerator for AzureSignedUrlGenerator {
+    fn sign(&self, path: &Path, validity: Duration, method: Method) -> DeltaResult<Url> {
+        let permissions = match method {
+            Method::GET | Method::HEAD => "r",
+            Method::PUT | Method::POST | Method::PATCH => "cw",
+            Method::DELETE => "d",
+            _ => "r",
+        };
+        let start = Utc::now();
+        let expiry = start + chrono::Duration::from_std(validity)
+            .map_err(|e| DeltaTableError::Generic(format!("validity out of range: {e}")))?;
+        let start = start.to_rfc3339_opts(SecondsFormat::Secs, true);
+        let expiry = expiry.to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        let canonicalized_resource =
+            format!("/blob/{}/{}/{}", self.account_name, self.container, path.as_ref());
+        let signed_version = AZURE_SAS_SIGNED_VERSION;
+        let string_to_sign =
+            sas_string_to_sign(permissions, &start, &expiry, &canonicalized_resource, signed_version);
+        let signature = base64::engine::general_purpose::STANDARD
+            .encode(hmac_sha256(&self.account_key, &string_to_sign)?);
+
+        let mut url = Url::parse(&format!(
+            "https://{}.blob.core.windows.net/{}/{}",
+            self.account_name,
+            self.container,
+            path.as_ref()
+        ))
+        .map_err(|e| DeltaTableError::Generic(format!("invalid azure url: {e}")))?;
+
+        let query = [
+            ("sv", signed_version),
+            ("sp", permissions),
+            ("st", &start),
+            ("se", &expiry),
+            ("sr", "b"),
+            ("sig", &signature),
+        ]
+        .iter()
+        .map(|(k, v)| format!("{k}={}", percent_encode(v)))
+        .collect::<Vec<_>>()
+        .join("&");
+        url.set_query(Some(&query));
+        Ok(url)
+    }
+}
+
+/// Percent-encode per the AWS SigV4 / Azure SAS "unreserved characters"
+/// rule: everything except `A-Za-z0-9-_.~` is escaped.
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pins the field *count* and *order* of the SAS string-to-sign against
+    /// the documented `sv >= 2020-12-06` layout
+    /// (permissions, start, expiry, resource, identifier, ip, protocol,
+    /// version, resource-type, snapshot-time, encryption-scope, rscc, rscd,
+    /// rsce, rscl, rsct = 16 fields). This checks the layout directly
+    /// instead of recomputing a signature through the same HMAC helper the
+    /// production path uses, which would just pin the code against itself.
+    #[test]
+    fn sas_string_to_sign_has_sixteen_fields_in_order() {
+        let string_to_sign = sas_string_to_sign(
+            "r",
+            "2024-01-01T00:00:00Z",
+            "2024-01-01T01:00:00Z",
+            "/blob/myaccount/mycontainer/data/file.parquet",
+            AZURE_SAS_SIGNED_VERSION,
+        );
+        let fields: Vec<&str> = string_to_sign.split('\n').collect();
+
+        assert_eq!(fields.len(), 16, "expected 16 fields, got {fields:?}");
+        assert_eq!(
+            fields,
+            vec![
+                "r",
+                "2024-01-01T00:00:00Z",
+                "2024-01-01T01:00:00Z",
+                "/blob/myaccount/mycontainer/data/file.parquet",
+                "",  // signedIdentifier
+                "",  // signedIP
+                "https",
+                AZURE_SAS_SIGNED_VERSION,
+                "b", // signedResource
+                "",  // signedSnapshotTime
+                "",  // signedEncryptionScope
+                "",  // rscc
+                "",  // rscd
+                "",  // rsce
+                "",  // rscl
+                "",  // rsct
+            ]
+        );
+    }
+}